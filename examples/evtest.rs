@@ -19,10 +19,13 @@ fn main() {
     };
     println!("{}", d);
     println!("Events:");
+
+    let mut wait = evdev::WaitContext::new().unwrap();
+    wait.add(&d, ()).unwrap();
     loop {
+        wait.wait(None).unwrap();
         for ev in d.fetch_events().unwrap() {
             println!("{:?}", ev);
         }
-        std::thread::sleep(std::time::Duration::from_secs(1));
     }
 }
@@ -1,19 +1,28 @@
 use bitvec::prelude::*;
 use std::fmt;
+use std::iter::FromIterator;
+use std::marker::PhantomData;
+use std::mem;
+use std::ops::Deref;
 
-#[derive(Copy, Clone)]
-pub struct AttributeSet<'a, T> {
-    bitslice: &'a BitSlice<Lsb0, u8>,
-    _indexer: std::marker::PhantomData<T>,
+/// A borrowed, read-only set of `T`.
+///
+/// This is a thin, `repr(transparent)` wrapper around a `BitSlice`, so it can be built from
+/// any bit buffer evdev hands back (e.g. the capability bits read off a real device) without
+/// copying. [`AttributeSet`] is the owned counterpart, for callers building a set up from
+/// scratch; it `Deref`s to this type so the two can be used interchangeably.
+#[repr(transparent)]
+pub struct AttributeSetRef<T> {
+    _indexer: PhantomData<T>,
+    bitslice: BitSlice<Lsb0, u8>,
 }
 
-impl<'a, T: EvdevEnum> AttributeSet<'a, T> {
+impl<T: EvdevEnum> AttributeSetRef<T> {
     #[inline]
-    pub(crate) fn new(bitslice: &'a BitSlice<Lsb0, u8>) -> Self {
-        Self {
-            bitslice,
-            _indexer: std::marker::PhantomData,
-        }
+    pub(crate) fn new(bitslice: &BitSlice<Lsb0, u8>) -> &Self {
+        // SAFETY: `AttributeSetRef` is `repr(transparent)` over `BitSlice<Lsb0, u8>`, so the
+        // two share layout; only the (zero-sized) indexer marker differs.
+        unsafe { mem::transmute(bitslice) }
     }
 
     #[inline]
@@ -22,17 +31,140 @@ impl<'a, T: EvdevEnum> AttributeSet<'a, T> {
     }
 
     #[inline]
-    pub fn iter(&self) -> impl Iterator<Item = T> + 'a {
+    pub fn iter(&self) -> impl Iterator<Item = T> + '_ {
         self.bitslice.iter_ones().map(T::from_index)
     }
 }
 
-impl<'a, T: EvdevEnum + fmt::Debug> fmt::Debug for AttributeSet<'a, T> {
+impl<T: EvdevEnum + fmt::Debug> fmt::Debug for AttributeSetRef<T> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         f.debug_set().entries(self.iter()).finish()
     }
 }
 
+/// An owned, mutable set of `T`, for building up capability sets (e.g. for
+/// [`VirtualDeviceBuilder`](crate::VirtualDeviceBuilder)) without having to hand-build a raw
+/// bit buffer.
+///
+/// The backing bitvec grows on demand as members are inserted, so there's no need to know the
+/// largest member of `T` up front.
+pub struct AttributeSet<T> {
+    bitvec: BitVec<Lsb0, u8>,
+    _indexer: PhantomData<T>,
+}
+
+impl<T: EvdevEnum> AttributeSet<T> {
+    #[inline]
+    pub fn new() -> Self {
+        AttributeSet {
+            bitvec: BitVec::new(),
+            _indexer: PhantomData,
+        }
+    }
+
+    pub fn insert(&mut self, attr: T) {
+        let index = attr.to_index();
+        if index >= self.bitvec.len() {
+            self.bitvec.resize(index + 1, false);
+        }
+        self.bitvec.set(index, true);
+    }
+
+    pub fn remove(&mut self, attr: T) {
+        if let Some(mut bit) = self.bitvec.get_mut(attr.to_index()) {
+            *bit = false;
+        }
+    }
+
+    /// The set of attributes present in either `self` or `other`.
+    pub fn union(&self, other: &Self) -> Self {
+        let (lhs, rhs) = self.pad_to_match(other);
+        Self::from_bitvec(lhs | rhs)
+    }
+
+    /// The set of attributes present in both `self` and `other`.
+    pub fn intersection(&self, other: &Self) -> Self {
+        let (lhs, rhs) = self.pad_to_match(other);
+        Self::from_bitvec(lhs & rhs)
+    }
+
+    /// The set of attributes present in `self` but not `other`.
+    pub fn difference(&self, other: &Self) -> Self {
+        let (lhs, rhs) = self.pad_to_match(other);
+        Self::from_bitvec(lhs & !rhs)
+    }
+
+    /// Clone both operands' backing bitvecs, padded with `false` to a common length, so
+    /// `bitvec`'s slice-level bitwise ops can be applied directly.
+    fn pad_to_match(&self, other: &Self) -> (BitVec<Lsb0, u8>, BitVec<Lsb0, u8>) {
+        let len = self.bitvec.len().max(other.bitvec.len());
+
+        let mut lhs = self.bitvec.clone();
+        lhs.resize(len, false);
+
+        let mut rhs = other.bitvec.clone();
+        rhs.resize(len, false);
+
+        (lhs, rhs)
+    }
+
+    #[inline]
+    fn from_bitvec(bitvec: BitVec<Lsb0, u8>) -> Self {
+        AttributeSet {
+            bitvec,
+            _indexer: PhantomData,
+        }
+    }
+}
+
+impl<T: EvdevEnum> Default for AttributeSet<T> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Clone for AttributeSet<T> {
+    fn clone(&self) -> Self {
+        AttributeSet {
+            bitvec: self.bitvec.clone(),
+            _indexer: PhantomData,
+        }
+    }
+}
+
+impl<T: EvdevEnum> FromIterator<T> for AttributeSet<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut set = Self::new();
+        for attr in iter {
+            set.insert(attr);
+        }
+        set
+    }
+}
+
+impl<T: EvdevEnum> Deref for AttributeSet<T> {
+    type Target = AttributeSetRef<T>;
+
+    #[inline]
+    fn deref(&self) -> &AttributeSetRef<T> {
+        AttributeSetRef::new(&self.bitvec)
+    }
+}
+
+impl<T: EvdevEnum> AsRef<AttributeSetRef<T>> for AttributeSet<T> {
+    #[inline]
+    fn as_ref(&self) -> &AttributeSetRef<T> {
+        self
+    }
+}
+
+impl<T: EvdevEnum + fmt::Debug> fmt::Debug for AttributeSet<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
 pub trait EvdevEnum: Copy + 'static {
     fn from_index(i: usize) -> Self;
     fn to_index(self) -> usize;
@@ -63,3 +195,77 @@ macro_rules! evdev_enum {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Copy, Clone, PartialEq, Eq)]
+    struct TestAttr(u16);
+
+    evdev_enum!(
+        TestAttr,
+        A = 0,
+        B = 1,
+        C = 5,
+    );
+
+    #[test]
+    fn union_pads_the_shorter_operand() {
+        let mut a = AttributeSet::<TestAttr>::new();
+        a.insert(TestAttr::A);
+        let mut b = AttributeSet::<TestAttr>::new();
+        b.insert(TestAttr::C);
+
+        let set = a.union(&b);
+        assert!(set.contains(TestAttr::A));
+        assert!(set.contains(TestAttr::C));
+        assert!(!set.contains(TestAttr::B));
+    }
+
+    #[test]
+    fn intersection_pads_the_shorter_operand() {
+        let mut a = AttributeSet::<TestAttr>::new();
+        a.insert(TestAttr::A);
+        a.insert(TestAttr::C);
+        let mut b = AttributeSet::<TestAttr>::new();
+        b.insert(TestAttr::A);
+
+        let set = a.intersection(&b);
+        assert!(set.contains(TestAttr::A));
+        assert!(!set.contains(TestAttr::C));
+    }
+
+    #[test]
+    fn difference_pads_the_shorter_operand() {
+        let mut a = AttributeSet::<TestAttr>::new();
+        a.insert(TestAttr::A);
+        a.insert(TestAttr::C);
+        let mut b = AttributeSet::<TestAttr>::new();
+        b.insert(TestAttr::A);
+
+        let set = a.difference(&b);
+        assert!(!set.contains(TestAttr::A));
+        assert!(set.contains(TestAttr::C));
+    }
+
+    #[test]
+    fn remove_absent_or_out_of_range_is_a_noop() {
+        let mut set = AttributeSet::<TestAttr>::new();
+        set.remove(TestAttr::A);
+        assert!(!set.contains(TestAttr::A));
+
+        set.insert(TestAttr::A);
+        set.remove(TestAttr::C);
+        assert!(set.contains(TestAttr::A));
+        assert!(!set.contains(TestAttr::C));
+    }
+
+    #[test]
+    fn from_iterator() {
+        let set: AttributeSet<TestAttr> = vec![TestAttr::A, TestAttr::C].into_iter().collect();
+        assert!(set.contains(TestAttr::A));
+        assert!(set.contains(TestAttr::C));
+        assert!(!set.contains(TestAttr::B));
+    }
+}
@@ -0,0 +1,88 @@
+//! Asynchronous event streams, enabled via the `tokio` feature.
+
+#![cfg(feature = "tokio")]
+
+use crate::{Device, InputEvent};
+use futures_core::stream::Stream;
+use std::collections::VecDeque;
+use std::io;
+use std::os::unix::io::{AsRawFd, RawFd};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use tokio::io::unix::AsyncFd;
+
+impl Device {
+    /// Turn this device into a `Stream` of its input events.
+    ///
+    /// This puts the underlying fd into `O_NONBLOCK` mode, so polling this device with the
+    /// blocking `fetch_events` afterwards is no longer meaningful.
+    pub fn into_event_stream(self) -> io::Result<EventStream> {
+        EventStream::new(self)
+    }
+}
+
+/// A `Stream` of `InputEvent`s read from a `Device`.
+///
+/// Obtained via [`Device::into_event_stream`].
+pub struct EventStream {
+    device: AsyncFd<Device>,
+    queue: VecDeque<InputEvent>,
+}
+
+impl EventStream {
+    pub(crate) fn new(device: Device) -> io::Result<Self> {
+        device.set_nonblocking(true)?;
+
+        Ok(EventStream {
+            device: AsyncFd::new(device)?,
+            queue: VecDeque::new(),
+        })
+    }
+
+    /// Returns a reference to the underlying device.
+    pub fn device(&self) -> &Device {
+        self.device.get_ref()
+    }
+}
+
+impl AsRawFd for EventStream {
+    fn as_raw_fd(&self) -> RawFd {
+        self.device.get_ref().as_raw_fd()
+    }
+}
+
+impl Stream for EventStream {
+    type Item = io::Result<InputEvent>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+
+        if let Some(ev) = this.queue.pop_front() {
+            return Poll::Ready(Some(Ok(ev)));
+        }
+
+        loop {
+            let mut guard = match this.device.poll_read_ready_mut(cx) {
+                Poll::Ready(Ok(guard)) => guard,
+                Poll::Ready(Err(e)) => return Poll::Ready(Some(Err(e))),
+                Poll::Pending => return Poll::Pending,
+            };
+
+            // `fetch_events` already resyncs on `SYN_DROPPED` internally, so a successful read
+            // here can be handed straight to the caller.
+            match guard.try_io(|inner| inner.get_mut().fetch_events()) {
+                Ok(Ok(events)) => {
+                    this.queue.extend(events);
+                    match this.queue.pop_front() {
+                        Some(ev) => return Poll::Ready(Some(Ok(ev))),
+                        // Woken with nothing to decode (e.g. a bare SYN_REPORT); wait again.
+                        None => continue,
+                    }
+                }
+                Ok(Err(e)) => return Poll::Ready(Some(Err(e))),
+                // `try_io` reports the read would have blocked; readiness was already cleared.
+                Err(_would_block) => continue,
+            }
+        }
+    }
+}
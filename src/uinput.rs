@@ -3,8 +3,9 @@
 //! This is quite useful when testing/debugging devices, or synchronization.
 
 use crate::constants::EventType;
-use crate::{nix_err, sys, AttributeSetRef, InputEvent, Key, RelativeAxisType};
+use crate::{nix_err, sys, AbsoluteAxisType, AttributeSetRef, InputEvent, Key, RelativeAxisType};
 use libc::O_NONBLOCK;
+use std::fmt;
 use std::fs::{File, OpenOptions};
 use std::io::{self, Write};
 use std::os::unix::{fs::OpenOptionsExt, io::AsRawFd};
@@ -12,6 +13,35 @@ use std::os::unix::{fs::OpenOptionsExt, io::AsRawFd};
 const BUS_USB: u16 = 0x03;
 const UINPUT_PATH: &str = "/dev/uinput";
 
+/// An absolute axis and the `value`/`minimum`/`maximum`/`fuzz`/`flat`/`resolution` the kernel
+/// should set up for it, for use with [`VirtualDeviceBuilder::with_absolute_axes`].
+#[derive(Copy, Clone)]
+pub struct UinputAbsSetup {
+    code: AbsoluteAxisType,
+    absinfo: libc::input_absinfo,
+}
+
+impl UinputAbsSetup {
+    #[inline]
+    pub fn new(code: AbsoluteAxisType, absinfo: libc::input_absinfo) -> Self {
+        UinputAbsSetup { code, absinfo }
+    }
+}
+
+impl fmt::Debug for UinputAbsSetup {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("UinputAbsSetup")
+            .field("code", &self.code)
+            .field("value", &self.absinfo.value)
+            .field("minimum", &self.absinfo.minimum)
+            .field("maximum", &self.absinfo.maximum)
+            .field("fuzz", &self.absinfo.fuzz)
+            .field("flat", &self.absinfo.flat)
+            .field("resolution", &self.absinfo.resolution)
+            .finish()
+    }
+}
+
 #[derive(Debug)]
 pub struct VirtualDeviceBuilder<'a> {
     file: File,
@@ -93,7 +123,73 @@ impl<'a> VirtualDeviceBuilder<'a> {
         Ok(self)
     }
 
+    pub fn with_absolute_axes(
+        self,
+        axes: &AttributeSetRef<AbsoluteAxisType>,
+        axis_info: &[UinputAbsSetup],
+    ) -> io::Result<Self> {
+        unsafe {
+            sys::ui_set_evbit(
+                self.file.as_raw_fd(),
+                crate::EventType::ABSOLUTE.0 as nix::sys::ioctl::ioctl_param_type,
+            )
+        }
+        .map_err(nix_err)?;
+
+        for bit in axes.iter() {
+            unsafe {
+                sys::ui_set_absbit(
+                    self.file.as_raw_fd(),
+                    bit.0 as nix::sys::ioctl::ioctl_param_type,
+                )
+            }
+            .map_err(nix_err)?;
+        }
+
+        for setup in axis_info {
+            let uabs = libc::uinput_abs_setup {
+                code: setup.code.0,
+                absinfo: setup.absinfo,
+            };
+
+            // Push value/minimum/maximum/fuzz/flat/resolution for this axis.
+            unsafe { sys::ui_abs_setup(self.file.as_raw_fd(), &uabs) }.map_err(nix_err)?;
+        }
+
+        Ok(self)
+    }
+
     pub fn build(self) -> io::Result<VirtualDevice> {
+        self.register()?;
+
+        Ok(VirtualDevice {
+            sink: self.file.try_clone()?,
+            _uinput: self.file,
+        })
+    }
+
+    /// Like `build`, but events are serialized to `sink` instead of being written back through
+    /// `/dev/uinput`.
+    pub fn build_with_sink<S: EventSink>(self, sink: S) -> io::Result<VirtualDevice<S>> {
+        self.register()?;
+
+        Ok(VirtualDevice {
+            sink,
+            _uinput: self.file,
+        })
+    }
+
+    /// Push the accumulated `uinput_setup` and create the device with the kernel.
+    fn register(&self) -> io::Result<()> {
+        let usetup = self.usetup();
+
+        unsafe { sys::ui_dev_setup(self.file.as_raw_fd(), &usetup) }.map_err(nix_err)?;
+        unsafe { sys::ui_dev_create(self.file.as_raw_fd()) }.map_err(nix_err)?;
+
+        Ok(())
+    }
+
+    fn usetup(&self) -> libc::uinput_setup {
         // Populate the uinput_setup struct
 
         let mut usetup = libc::uinput_setup {
@@ -110,7 +206,7 @@ impl<'a> VirtualDeviceBuilder<'a> {
         assert!(name_bytes.len() + 1 < libc::UINPUT_MAX_NAME_SIZE);
         usetup.name[..name_bytes.len()].copy_from_slice(name_bytes);
 
-        VirtualDevice::new(self.file, &usetup)
+        usetup
     }
 }
 
@@ -121,23 +217,27 @@ const DEFAULT_ID: libc::input_id = libc::input_id {
     version: 0x111,
 };
 
-pub struct VirtualDevice {
-    file: File,
-}
+/// Something `VirtualDevice::emit` can serialize events into.
+///
+/// The default (`S = File`) writes straight to `/dev/uinput`; implement this for any other
+/// `Write` to send events somewhere else instead.
+pub trait EventSink: Write {}
 
-impl VirtualDevice {
-    /// Create a new virtual device.
-    fn new(file: File, usetup: &libc::uinput_setup) -> io::Result<Self> {
-        unsafe { sys::ui_dev_setup(file.as_raw_fd(), usetup) }.map_err(nix_err)?;
-        unsafe { sys::ui_dev_create(file.as_raw_fd()) }.map_err(nix_err)?;
+impl<W: Write> EventSink for W {}
 
-        Ok(VirtualDevice { file })
-    }
+pub struct VirtualDevice<S: EventSink = File> {
+    sink: S,
+    /// Keeps the uinput control fd open so the kernel doesn't tear the device down; the
+    /// device was registered through it in `build`/`build_with_sink` but events may no longer
+    /// flow through it directly (see `sink`).
+    _uinput: File,
+}
 
+impl<S: EventSink> VirtualDevice<S> {
     #[inline]
     fn write_raw(&mut self, messages: &[InputEvent]) -> io::Result<()> {
         let bytes = unsafe { crate::cast_to_bytes(messages) };
-        self.file.write_all(bytes)
+        self.sink.write_all(bytes)
     }
 
     /// Post a set of messages to the virtual device.
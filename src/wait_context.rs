@@ -0,0 +1,152 @@
+//! An `epoll`-backed wait context for watching several devices from one thread.
+//!
+//! Register a device's fd together with a caller-chosen token via [`WaitContext::add`], then
+//! block in [`WaitContext::wait`] until one or more of the registered fds are ready.
+
+use crate::Device;
+use std::collections::HashMap;
+use std::io;
+use std::os::unix::io::{AsRawFd, RawFd};
+use std::time::Duration;
+
+/// Readiness reported for a single token by [`WaitContext::wait`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct ReadyFlags {
+    /// The device has events available to read.
+    pub readable: bool,
+    /// The device hung up or errored (e.g. it was unplugged) and should be dropped by the
+    /// caller; its fd is no longer usable and has already been forgotten by this context.
+    pub disconnected: bool,
+}
+
+/// A set of devices that can be waited on together via `epoll`.
+pub struct WaitContext<T> {
+    epoll_fd: RawFd,
+    tokens: HashMap<RawFd, T>,
+    events: Vec<libc::epoll_event>,
+}
+
+impl<T> WaitContext<T> {
+    /// Create a new, empty wait context.
+    pub fn new() -> io::Result<Self> {
+        let epoll_fd = unsafe { libc::epoll_create1(libc::EPOLL_CLOEXEC) };
+        if epoll_fd < 0 {
+            return Err(io::Error::last_os_error());
+        }
+
+        Ok(WaitContext {
+            epoll_fd,
+            tokens: HashMap::new(),
+            events: Vec::new(),
+        })
+    }
+
+    /// Start watching `device` for readability, tagging its readiness with `token`.
+    pub fn add(&mut self, device: &Device, token: T) -> io::Result<()> {
+        let fd = device.as_raw_fd();
+        let mut event = libc::epoll_event {
+            events: libc::EPOLLIN as u32,
+            u64: fd as u64,
+        };
+
+        let res = unsafe { libc::epoll_ctl(self.epoll_fd, libc::EPOLL_CTL_ADD, fd, &mut event) };
+        if res < 0 {
+            return Err(io::Error::last_os_error());
+        }
+
+        self.tokens.insert(fd, token);
+        self.events.resize_with(self.tokens.len(), || unsafe { std::mem::zeroed() });
+
+        Ok(())
+    }
+
+    /// Stop watching `device`.
+    pub fn delete(&mut self, device: &Device) -> io::Result<()> {
+        let fd = device.as_raw_fd();
+
+        let res = unsafe {
+            libc::epoll_ctl(self.epoll_fd, libc::EPOLL_CTL_DEL, fd, std::ptr::null_mut())
+        };
+        if res < 0 {
+            return Err(io::Error::last_os_error());
+        }
+
+        self.tokens.remove(&fd);
+
+        Ok(())
+    }
+}
+
+impl<T: Clone> WaitContext<T> {
+    /// Block until at least one registered device is ready, or `timeout` elapses.
+    ///
+    /// Passing `None` waits indefinitely.
+    pub fn wait(&mut self, timeout: Option<Duration>) -> io::Result<Vec<(T, ReadyFlags)>> {
+        if self.events.is_empty() {
+            self.events.push(unsafe { std::mem::zeroed() });
+        }
+
+        let timeout_ms = match timeout {
+            Some(d) => d.as_millis().min(i32::MAX as u128) as libc::c_int,
+            None => -1,
+        };
+
+        let n = loop {
+            let res = unsafe {
+                libc::epoll_wait(
+                    self.epoll_fd,
+                    self.events.as_mut_ptr(),
+                    self.events.len() as libc::c_int,
+                    timeout_ms,
+                )
+            };
+
+            if res < 0 {
+                let err = io::Error::last_os_error();
+                if err.kind() == io::ErrorKind::Interrupted {
+                    continue;
+                }
+                return Err(err);
+            }
+
+            break res as usize;
+        };
+
+        let mut ready = Vec::with_capacity(n);
+        for event in &self.events[..n] {
+            let fd = event.u64 as RawFd;
+            let disconnected = event.events & (libc::EPOLLHUP | libc::EPOLLERR) as u32 != 0;
+
+            let token = if disconnected {
+                // Drop the registration so callers don't have to remember to call `delete`
+                // themselves, and so a later `epoll_wait` never reports this fd again.
+                let _ = unsafe {
+                    libc::epoll_ctl(self.epoll_fd, libc::EPOLL_CTL_DEL, fd, std::ptr::null_mut())
+                };
+                match self.tokens.remove(&fd) {
+                    Some(token) => token,
+                    None => continue,
+                }
+            } else {
+                match self.tokens.get(&fd) {
+                    Some(token) => token.clone(),
+                    None => continue,
+                }
+            };
+
+            let flags = ReadyFlags {
+                readable: event.events & libc::EPOLLIN as u32 != 0,
+                disconnected,
+            };
+            ready.push((token, flags));
+        }
+
+        Ok(ready)
+    }
+}
+
+impl<T> Drop for WaitContext<T> {
+    fn drop(&mut self) {
+        unsafe { libc::close(self.epoll_fd) };
+    }
+}